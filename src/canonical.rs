@@ -0,0 +1,359 @@
+use std::fmt::Write as _;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::Error;
+
+/// Serialize `value` into a canonical byte form suitable for signing
+///
+/// Borrowed from radicle-link's canonical-JSON approach: object keys are
+/// sorted lexicographically by UTF-8 bytes, there is no insignificant
+/// whitespace, strings are emitted as UTF-8 with minimal escaping, integers
+/// are written without leading zeros, and floating-point/NaN values are
+/// rejected. The output is byte-for-byte reproducible across platforms, so a
+/// signature produced on one device verifies on another regardless of map
+/// iteration order.
+pub fn to_vec<T: Serialize>(value: &T) -> Result<Vec<u8>, Error> {
+    // `serde_json::to_value` silently turns non-finite f64s (NaN, +/-Infinity)
+    // into `Value::Null`, which would let them sneak past the `Value::Number`
+    // check below. Walk the value first with a serializer that only cares
+    // about spotting float primitives, so NaN/Infinity are rejected the same
+    // as any other float.
+    value.serialize(RejectFloats)?;
+
+    let value = serde_json::to_value(value)?;
+
+    let mut out = String::new();
+    write_canonical(&value, &mut out)?;
+
+    Ok(out.into_bytes())
+}
+
+/// A [serde::Serializer] that performs no output, only rejecting `f32`/`f64`
+/// primitives anywhere in the value tree (including NaN/Infinity, which
+/// `serde_json` would otherwise collapse into `null` before they can be
+/// told apart from an actual `null`)
+struct RejectFloats;
+
+impl serde::Serializer for RejectFloats {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, _v: bool) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_i8(self, _v: i8) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_i16(self, _v: i16) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_i32(self, _v: i32) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_i64(self, _v: i64) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_u8(self, _v: u8) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_u16(self, _v: u16) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_u32(self, _v: u32) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_u64(self, _v: u64) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_f32(self, _v: f32) -> Result<(), Error> {
+        Err(Error::NonCanonical)
+    }
+    fn serialize_f64(self, _v: f64) -> Result<(), Error> {
+        Err(Error::NonCanonical)
+    }
+    fn serialize_char(self, _v: char) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_str(self, _v: &str) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        Ok(self)
+    }
+}
+
+impl serde::ser::SerializeSeq for RejectFloats {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(RejectFloats)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeTuple for RejectFloats {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(RejectFloats)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeTupleStruct for RejectFloats {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(RejectFloats)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeTupleVariant for RejectFloats {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(RejectFloats)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeMap for RejectFloats {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        key.serialize(RejectFloats)
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(RejectFloats)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeStruct for RejectFloats {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(RejectFloats)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl serde::ser::SerializeStructVariant for RejectFloats {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(RejectFloats)
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+fn write_canonical(value: &Value, out: &mut String) -> Result<(), Error> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => {
+            if n.is_f64() {
+                return Err(Error::NonCanonical);
+            }
+            out.push_str(&n.to_string());
+        }
+        Value::String(s) => write_canonical_string(s, out),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            out.push('{');
+
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+
+            for (i, key) in keys.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_string(key, out);
+                out.push(':');
+                write_canonical(&map[*key], out)?;
+            }
+
+            out.push('}');
+        }
+    }
+
+    Ok(())
+}
+
+fn write_canonical_string(s: &str, out: &mut String) {
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+#[cfg(test)]
+mod test {
+    use super::to_vec;
+
+    #[test]
+    fn test_key_order_is_sorted() {
+        let a = serde_json::json!({"b": 1, "a": 2});
+        let b = serde_json::json!({"a": 2, "b": 1});
+
+        assert_eq!(to_vec(&a).unwrap(), to_vec(&b).unwrap());
+        assert_eq!(to_vec(&a).unwrap(), br#"{"a":2,"b":1}"#);
+    }
+
+    #[test]
+    fn test_rejects_floats() {
+        let v = serde_json::json!({"a": 1.5});
+
+        assert!(to_vec(&v).is_err());
+    }
+
+    #[test]
+    fn test_rejects_nan_and_infinity() {
+        #[derive(serde::Serialize)]
+        struct Payload {
+            a: f64,
+        }
+
+        assert!(to_vec(&Payload { a: f64::NAN }).is_err());
+        assert!(to_vec(&Payload {
+            a: f64::INFINITY
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::{DeviceID, Error, Identity, PublicIdentity};
+
+/// A payload together with signatures over it, modeled on TUF's signed-role envelope
+///
+/// Multiple [Identity] keys may sign the same payload, allowing a
+/// [Delegation] to require K-of-N signers before the metadata is trusted.
+#[derive(Clone, Debug)]
+pub struct SignedMetadata<T> {
+    pub payload: T,
+    pub signatures: BTreeMap<DeviceID, Vec<u8>>,
+}
+
+impl<T: Serialize> SignedMetadata<T> {
+    /// Wrap a payload with no signatures yet
+    pub fn new(payload: T) -> SignedMetadata<T> {
+        SignedMetadata {
+            payload,
+            signatures: BTreeMap::new(),
+        }
+    }
+
+    /// Sign the payload with `identity`, adding or replacing its signature
+    pub fn sign(&mut self, identity: &Identity) -> Result<(), Error> {
+        let bytes = crate::canonical::to_vec(&self.payload)?;
+        let signature = identity.sign(&bytes)?;
+
+        self.signatures.insert(identity.id(), signature);
+
+        Ok(())
+    }
+
+    /// Check whether `delegation` has enough valid, distinct signers over the payload
+    ///
+    /// Duplicate signatures from the same [DeviceID] count once, even if
+    /// `delegation.keys` itself lists that `DeviceID` more than once. A
+    /// signature from a [DeviceID] that is not part of `delegation` is
+    /// ignored rather than treated as an error.
+    pub fn verify(&self, delegation: &Delegation) -> Result<bool, Error> {
+        let bytes = crate::canonical::to_vec(&self.payload)?;
+
+        let mut valid_signers = HashSet::new();
+        for key in &delegation.keys {
+            let Some(signature) = self.signatures.get(&key.id()) else {
+                continue;
+            };
+
+            if key.verify(&bytes, signature)? {
+                valid_signers.insert(key.id());
+            }
+        }
+
+        Ok(valid_signers.len() >= delegation.threshold)
+    }
+}
+
+/// A set of keys authorized to sign metadata, with the minimum number required
+#[derive(Clone, Debug)]
+pub struct Delegation {
+    pub keys: Vec<PublicIdentity>,
+    pub threshold: usize,
+}
+
+impl Delegation {
+    /// Create a new delegation requiring `threshold` of `keys` to sign
+    pub fn new(keys: Vec<PublicIdentity>, threshold: usize) -> Delegation {
+        Delegation { keys, threshold }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Identity;
+
+    use super::{Delegation, SignedMetadata};
+
+    #[test]
+    fn test_threshold_met_and_unmet() {
+        let a = Identity::generate().unwrap();
+        let b = Identity::generate().unwrap();
+        let c = Identity::generate().unwrap();
+
+        let delegation = Delegation::new(
+            vec![
+                a.public_identity().unwrap(),
+                b.public_identity().unwrap(),
+                c.public_identity().unwrap(),
+            ],
+            2,
+        );
+
+        let mut metadata = SignedMetadata::new("payload".to_string());
+        assert!(!metadata.verify(&delegation).unwrap());
+
+        metadata.sign(&a).unwrap();
+        assert!(!metadata.verify(&delegation).unwrap());
+
+        metadata.sign(&b).unwrap();
+        assert!(metadata.verify(&delegation).unwrap());
+    }
+
+    #[test]
+    fn test_duplicate_delegation_key_does_not_double_count() {
+        let a = Identity::generate().unwrap();
+        let b = Identity::generate().unwrap();
+
+        let delegation = Delegation::new(
+            vec![
+                a.public_identity().unwrap(),
+                a.public_identity().unwrap(),
+                b.public_identity().unwrap(),
+            ],
+            2,
+        );
+
+        let mut metadata = SignedMetadata::new("payload".to_string());
+        metadata.sign(&a).unwrap();
+
+        // Only `a` signed; listing `a` twice in the delegation must not
+        // satisfy a threshold of 2 on its own.
+        assert!(!metadata.verify(&delegation).unwrap());
+    }
+
+    #[test]
+    fn test_unknown_signer_is_ignored() {
+        let a = Identity::generate().unwrap();
+        let stranger = Identity::generate().unwrap();
+
+        let delegation = Delegation::new(vec![a.public_identity().unwrap()], 1);
+
+        let mut metadata = SignedMetadata::new("payload".to_string());
+        metadata.sign(&a).unwrap();
+        metadata.sign(&stranger).unwrap();
+
+        assert!(metadata.verify(&delegation).unwrap());
+    }
+}
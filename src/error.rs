@@ -2,6 +2,32 @@
 pub enum Error {
     #[error("invalid id length")]
     InvalidLength,
+    #[error("unknown signature scheme")]
+    UnknownScheme,
+    #[error("unsupported signature scheme for this operation")]
+    UnsupportedScheme,
+    #[error("non-canonical value: floating point numbers are not allowed")]
+    NonCanonical,
+    #[error("serialize: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("{0}")]
+    Message(String),
+    #[error("invalid keystore file")]
+    InvalidKeystore,
+    #[error("wrong passphrase or tampered keystore file")]
+    DecryptionFailed,
+    #[error("rotation chain contains a broken or unverifiable link")]
+    BrokenRotationChain,
+    #[error("rotation chain contains a cycle")]
+    RotationCycle,
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
     #[error("openssl: {0}")]
     Openssl(#[from] openssl::error::ErrorStack),
 }
+
+impl serde::ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
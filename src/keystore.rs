@@ -0,0 +1,214 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use openssl::hash::MessageDigest;
+use openssl::pkcs5::pbkdf2_hmac;
+use openssl::pkey::{Id, PKey};
+use openssl::rand::rand_bytes;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use serde::{Deserialize, Serialize};
+
+use crate::{compute_address, public_key_bytes, DeviceID, Error, Identity, SignatureScheme};
+
+const KDF_PBKDF2_HMAC_SHA256: &str = "pbkdf2-hmac-sha256";
+const PBKDF2_ITERATIONS: u32 = 200_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// On-disk, passphrase-encrypted envelope for an [Identity]
+///
+/// The private key is encrypted with AES-256-GCM under a key derived from
+/// the passphrase via PBKDF2-HMAC-SHA256; salt, nonce, KDF parameters, the
+/// [DeviceID] and the ciphertext are stored alongside so the file is
+/// self-describing.
+#[derive(Serialize, Deserialize)]
+struct KeystoreEnvelope {
+    id: DeviceID,
+    scheme: String,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    tag: String,
+    ciphertext: String,
+}
+
+impl Identity {
+    /// Save this Identity to `path`, encrypting the private key with `passphrase`
+    pub fn save_to_file(
+        &self,
+        path: impl AsRef<Path>,
+        passphrase: impl AsRef<[u8]>,
+    ) -> Result<(), Error> {
+        let mut salt = [0u8; SALT_LEN];
+        rand_bytes(&mut salt)?;
+
+        let mut key = [0u8; KEY_LEN];
+        pbkdf2_hmac(
+            passphrase.as_ref(),
+            &salt,
+            PBKDF2_ITERATIONS as usize,
+            MessageDigest::sha256(),
+            &mut key,
+        )?;
+
+        let mut nonce = [0u8; NONCE_LEN];
+        rand_bytes(&mut nonce)?;
+
+        let mut tag = [0u8; 16];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &key,
+            Some(&nonce),
+            &[],
+            &self.raw_sign,
+            &mut tag,
+        )?;
+
+        let envelope = KeystoreEnvelope {
+            id: self.id,
+            scheme: self.scheme.to_string(),
+            kdf: KDF_PBKDF2_HMAC_SHA256.to_string(),
+            iterations: PBKDF2_ITERATIONS,
+            salt: STANDARD.encode(salt),
+            nonce: STANDARD.encode(nonce),
+            tag: STANDARD.encode(tag),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+
+        let json = serde_json::to_vec_pretty(&envelope)?;
+        std::fs::write(path, json)?;
+
+        Ok(())
+    }
+
+    /// Load an Identity previously written by [Identity::save_to_file]
+    ///
+    /// Fails with [Error::DecryptionFailed] rather than panicking when the
+    /// passphrase is wrong or the encrypted key (salt, nonce, tag or
+    /// ciphertext) has been tampered with. An unknown KDF, a field that is not
+    /// valid base64, or a decrypted key that does not match the stored
+    /// [DeviceID] fails with [Error::InvalidKeystore].
+    pub fn load_from_file(
+        path: impl AsRef<Path>,
+        passphrase: impl AsRef<[u8]>,
+    ) -> Result<Identity, Error> {
+        let json = std::fs::read(path)?;
+        let envelope: KeystoreEnvelope = serde_json::from_slice(&json)?;
+
+        if envelope.kdf != KDF_PBKDF2_HMAC_SHA256 {
+            return Err(Error::InvalidKeystore);
+        }
+
+        let scheme = SignatureScheme::from_str(&envelope.scheme)?;
+
+        let salt = STANDARD
+            .decode(&envelope.salt)
+            .map_err(|_| Error::InvalidKeystore)?;
+        let nonce = STANDARD
+            .decode(&envelope.nonce)
+            .map_err(|_| Error::InvalidKeystore)?;
+        let tag = STANDARD
+            .decode(&envelope.tag)
+            .map_err(|_| Error::InvalidKeystore)?;
+        let ciphertext = STANDARD
+            .decode(&envelope.ciphertext)
+            .map_err(|_| Error::InvalidKeystore)?;
+
+        let mut key = [0u8; KEY_LEN];
+        pbkdf2_hmac(
+            passphrase.as_ref(),
+            &salt,
+            envelope.iterations as usize,
+            MessageDigest::sha256(),
+            &mut key,
+        )?;
+
+        let raw_sign = decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &key,
+            Some(&nonce),
+            &[],
+            &ciphertext,
+            &tag,
+        )
+        .map_err(|_| Error::DecryptionFailed)?;
+
+        let pkey = match scheme {
+            SignatureScheme::Ed25519 => {
+                PKey::private_key_from_raw_bytes(&raw_sign, Id::ED25519)?
+            }
+            SignatureScheme::RsaPssSha256 | SignatureScheme::RsaPssSha512 => {
+                PKey::private_key_from_der(&raw_sign)?
+            }
+        };
+
+        let pk = public_key_bytes(&pkey, scheme)?;
+        let id = compute_address(scheme, &pk)?;
+        if id != envelope.id {
+            return Err(Error::InvalidKeystore);
+        }
+
+        Ok(Identity {
+            id,
+            scheme,
+            raw_sign,
+            pkey,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Error, Identity};
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("ringlink-identity-keystore-test-{name}.json"))
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let path = temp_path("roundtrip");
+        let identity = Identity::generate().unwrap();
+
+        identity.save_to_file(&path, "correct horse battery staple").unwrap();
+        let loaded = Identity::load_from_file(&path, "correct horse battery staple").unwrap();
+
+        assert_eq!(identity, loaded);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_wrong_passphrase_fails_cleanly() {
+        let path = temp_path("wrong-passphrase");
+        let identity = Identity::generate().unwrap();
+
+        identity.save_to_file(&path, "correct horse battery staple").unwrap();
+
+        let result = Identity::load_from_file(&path, "wrong passphrase");
+        assert!(matches!(result, Err(Error::DecryptionFailed)));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_fails_cleanly() {
+        let path = temp_path("tampered");
+        let identity = Identity::generate().unwrap();
+
+        identity.save_to_file(&path, "correct horse battery staple").unwrap();
+
+        let mut json: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        json["ciphertext"] = serde_json::Value::String("dGFtcGVyZWQ=".to_string());
+        std::fs::write(&path, serde_json::to_vec(&json).unwrap()).unwrap();
+
+        let result = Identity::load_from_file(&path, "correct horse battery staple");
+        assert!(matches!(result, Err(Error::DecryptionFailed)));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
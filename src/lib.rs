@@ -1,29 +1,100 @@
 use std::fmt::{Debug, Formatter};
 use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
 use openssl::hash::MessageDigest;
 use openssl::nid::Nid;
 use openssl::pkey::{HasPublic, Id, PKey, PKeyRef, Private, Public};
-use openssl::sign::{Signer, Verifier};
+use openssl::rsa::{Padding, Rsa};
+use openssl::sign::{RsaPssSaltlen, Signer, Verifier};
+use serde::Serialize;
 
 pub use hex;
 pub use serde;
 
+pub use delegation::{Delegation, SignedMetadata};
 pub use error::Error;
 pub use id::DeviceID;
+pub use rotation::{RotationChain, RotationRecord};
 
+pub mod canonical;
+mod delegation;
 mod error;
 mod id;
+mod keystore;
+mod rotation;
 mod ser;
 mod utils;
 
 /// digest method for compute id
 const NID_BLAKE2B512: i32 = 1056;
 
+/// bit size used when generating a new RSA key pair
+const RSA_KEY_BITS: u32 = 2048;
+
+/// length of a raw Ed25519 public key, in bytes
+const ED25519_PUBLIC_KEY_LEN: usize = 32;
+
+/// Signature scheme backing an [Identity] / [PublicIdentity]
+///
+/// The variant is mixed into [compute_address] as a one-byte domain
+/// separation tag, so keys of different schemes can never collide on the
+/// same [DeviceID].
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash, Default)]
+pub enum SignatureScheme {
+    #[default]
+    Ed25519,
+    RsaPssSha256,
+    RsaPssSha512,
+}
+
+impl SignatureScheme {
+    /// Domain-separation tag for [compute_address]
+    const fn tag(self) -> u8 {
+        match self {
+            SignatureScheme::Ed25519 => 0,
+            SignatureScheme::RsaPssSha256 => 1,
+            SignatureScheme::RsaPssSha512 => 2,
+        }
+    }
+
+    fn digest(self) -> Option<MessageDigest> {
+        match self {
+            SignatureScheme::Ed25519 => None,
+            SignatureScheme::RsaPssSha256 => Some(MessageDigest::sha256()),
+            SignatureScheme::RsaPssSha512 => Some(MessageDigest::sha512()),
+        }
+    }
+}
+
+impl ::core::fmt::Display for SignatureScheme {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.write_str(match self {
+            SignatureScheme::Ed25519 => "ed25519",
+            SignatureScheme::RsaPssSha256 => "rsa-pss-sha256",
+            SignatureScheme::RsaPssSha512 => "rsa-pss-sha512",
+        })
+    }
+}
+
+impl FromStr for SignatureScheme {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ed25519" => Ok(SignatureScheme::Ed25519),
+            "rsa-pss-sha256" => Ok(SignatureScheme::RsaPssSha256),
+            "rsa-pss-sha512" => Ok(SignatureScheme::RsaPssSha512),
+            _ => Err(Error::UnknownScheme),
+        }
+    }
+}
+
 /// RingLink identity
 #[derive(Clone)]
 pub struct Identity {
     id: DeviceID,
+    scheme: SignatureScheme,
     raw_sign: Vec<u8>,
     pkey: PKey<Private>,
 }
@@ -32,22 +103,41 @@ pub struct Identity {
 #[derive(Clone)]
 pub struct PublicIdentity {
     id: DeviceID,
+    scheme: SignatureScheme,
     raw_sign: Vec<u8>,
 
     pkey: PKey<Public>,
 }
 
 impl Identity {
-    /// Generate new RingLink Identity
+    /// Generate new RingLink Identity using the default [SignatureScheme::Ed25519] scheme
     pub fn generate() -> Result<Identity, Error> {
-        let sign = PKey::generate_ed25519()?;
+        Self::generate_with(SignatureScheme::Ed25519)
+    }
+
+    /// Generate new RingLink Identity using the given signature scheme
+    pub fn generate_with(scheme: SignatureScheme) -> Result<Identity, Error> {
+        let sign = match scheme {
+            SignatureScheme::Ed25519 => PKey::generate_ed25519()?,
+            SignatureScheme::RsaPssSha256 | SignatureScheme::RsaPssSha512 => {
+                PKey::from_rsa(Rsa::generate(RSA_KEY_BITS)?)?
+            }
+        };
 
-        let pk = sign.raw_public_key()?;
-        let id = compute_address(&pk)?;
+        let pk = public_key_bytes(&sign, scheme)?;
+        let id = compute_address(scheme, &pk)?;
+
+        let raw_sign = match scheme {
+            SignatureScheme::Ed25519 => sign.raw_private_key()?,
+            SignatureScheme::RsaPssSha256 | SignatureScheme::RsaPssSha512 => {
+                sign.private_key_to_der()?
+            }
+        };
 
         Ok(Identity {
             id,
-            raw_sign: sign.raw_private_key()?,
+            scheme,
+            raw_sign,
             pkey: sign,
         })
     }
@@ -57,11 +147,7 @@ impl Identity {
     /// # Arguments
     /// * `data` - Data to sign
     pub fn sign(&self, data: impl AsRef<[u8]>) -> Result<Vec<u8>, Error> {
-        let mut signer = Signer::new_without_digest(&self.pkey)?;
-
-        let signature = signer.sign_oneshot_to_vec(data.as_ref())?;
-
-        Ok(signature)
+        sign(&self.pkey, self.scheme, data)
     }
 
     /// Verify signature with Identity
@@ -70,7 +156,30 @@ impl Identity {
         data: impl AsRef<[u8]>,
         signature: impl AsRef<[u8]>,
     ) -> Result<bool, Error> {
-        verify(&self.pkey, data, signature)
+        verify(&self.pkey, self.scheme, data, signature)
+    }
+
+    /// Sign `value` after serializing it into [canonical] bytes
+    ///
+    /// Unlike [Identity::sign], which takes opaque bytes the caller must
+    /// frame themselves, this canonicalizes structured data first so the
+    /// same value always signs to the same bytes, regardless of platform or
+    /// map iteration order.
+    pub fn sign_canonical<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, Error> {
+        let bytes = canonical::to_vec(value)?;
+
+        self.sign(bytes)
+    }
+
+    /// Verify a signature produced by [Identity::sign_canonical]
+    pub fn verify_canonical<T: Serialize>(
+        &self,
+        value: &T,
+        signature: impl AsRef<[u8]>,
+    ) -> Result<bool, Error> {
+        let bytes = canonical::to_vec(value)?;
+
+        self.verify(bytes, signature)
     }
 
     /// Get public part of Identity
@@ -78,7 +187,8 @@ impl Identity {
     /// # Error
     /// Return error if public key is not available
     pub fn public_identity(&self) -> Result<PublicIdentity, Error> {
-        PublicIdentity::new_with_id(self.id, self.pkey.raw_public_key()?)
+        let pk = public_key_bytes(&self.pkey, self.scheme)?;
+        PublicIdentity::new_with_id(self.id, self.scheme, pk)
     }
 
     /// Unique ID of Identity
@@ -86,6 +196,11 @@ impl Identity {
         self.id
     }
 
+    /// Signature scheme backing this Identity
+    pub fn scheme(&self) -> SignatureScheme {
+        self.scheme
+    }
+
     /// Get raw private key
     pub fn private_key(&self) -> &[u8] {
         &self.raw_sign
@@ -111,8 +226,9 @@ impl Debug for Identity {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut f = f.debug_struct("Identity");
         f.field("id", &self.id);
+        f.field("scheme", &self.scheme);
 
-        match self.pkey.raw_public_key() {
+        match public_key_bytes(&self.pkey, self.scheme) {
             Ok(pk) => f.field("sign", &pk),
             Err(_) => f.field("sign", &"not available"),
         };
@@ -124,25 +240,39 @@ impl Debug for Identity {
 impl PublicIdentity {
     pub(crate) fn new_with_id(
         id: DeviceID,
+        scheme: SignatureScheme,
         sign: impl AsRef<[u8]>,
     ) -> Result<PublicIdentity, Error> {
-        let pkey = PKey::public_key_from_raw_bytes(sign.as_ref(), Id::ED25519)?;
+        let pkey = pkey_from_public_bytes(scheme, sign.as_ref())?;
 
         Ok(PublicIdentity {
             id,
+            scheme,
             raw_sign: sign.as_ref().to_vec(),
             pkey,
         })
     }
 
-    /// Construct PublicIdentity from public keys
+    /// Construct PublicIdentity from public keys, using the default [SignatureScheme::Ed25519] scheme
     ///
     /// # Arguments
     /// * `sign` - Public key for signing, normally get from [Identity::public_identity]
     pub fn new(sign: impl AsRef<[u8]>) -> Result<PublicIdentity, Error> {
-        let id = compute_address(sign.as_ref())?;
+        Self::new_with_scheme(SignatureScheme::Ed25519, sign)
+    }
 
-        Self::new_with_id(id, sign)
+    /// Construct PublicIdentity from public key bytes of the given scheme
+    ///
+    /// # Arguments
+    /// * `scheme` - Signature scheme the key bytes belong to
+    /// * `sign` - Public key for signing, normally get from [Identity::public_identity]
+    pub fn new_with_scheme(
+        scheme: SignatureScheme,
+        sign: impl AsRef<[u8]>,
+    ) -> Result<PublicIdentity, Error> {
+        let id = compute_address(scheme, sign.as_ref())?;
+
+        Self::new_with_id(id, scheme, sign)
     }
 
     /// Verify signature with [PublicIdentity]
@@ -151,7 +281,18 @@ impl PublicIdentity {
         data: impl AsRef<[u8]>,
         signature: impl AsRef<[u8]>,
     ) -> Result<bool, Error> {
-        verify(&self.pkey, data, signature)
+        verify(&self.pkey, self.scheme, data, signature)
+    }
+
+    /// Verify a signature produced by [Identity::sign_canonical]
+    pub fn verify_canonical<T: Serialize>(
+        &self,
+        value: &T,
+        signature: impl AsRef<[u8]>,
+    ) -> Result<bool, Error> {
+        let bytes = canonical::to_vec(value)?;
+
+        self.verify(bytes, signature)
     }
 
     /// Unique ID of Identity
@@ -159,12 +300,66 @@ impl PublicIdentity {
         self.id
     }
 
+    /// Signature scheme backing this PublicIdentity
+    pub fn scheme(&self) -> SignatureScheme {
+        self.scheme
+    }
+
     /// Get raw public key of Identity
     ///
     /// The returned value can be used to construct a new [PublicIdentity]
     pub fn public_key(&self) -> &[u8] {
         &self.raw_sign
     }
+
+    /// Export as a SubjectPublicKeyInfo DER blob
+    ///
+    /// Ed25519 keys use OID 1.3.101.112; RSA keys use the standard
+    /// `rsaEncryption` OID. This lets a `PublicIdentity` be handed to
+    /// OpenSSL CLI, web PKI libraries, or `openssl pkey` without callers
+    /// manually reconstructing raw key bytes.
+    pub fn to_spki_der(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.pkey.public_key_to_der()?)
+    }
+
+    /// Export as a SubjectPublicKeyInfo PEM blob
+    pub fn to_spki_pem(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.pkey.public_key_to_pem()?)
+    }
+
+    /// Import from a SubjectPublicKeyInfo DER blob
+    ///
+    /// The [DeviceID] is recomputed via `compute_address` rather than
+    /// trusted from the DER, and the embedded key length is validated
+    /// against the raw Ed25519 public key size. Only Ed25519 SPKI blobs are
+    /// supported on import: a plain `rsaEncryption` SPKI has no way to tell
+    /// apart [SignatureScheme::RsaPssSha256] from [SignatureScheme::RsaPssSha512],
+    /// so there is no scheme to recover it into.
+    pub fn from_spki_der(der: impl AsRef<[u8]>) -> Result<PublicIdentity, Error> {
+        let pkey = PKey::public_key_from_der(der.as_ref())?;
+
+        Self::from_spki_pkey(pkey)
+    }
+
+    /// Import from a SubjectPublicKeyInfo PEM blob
+    pub fn from_spki_pem(pem: impl AsRef<[u8]>) -> Result<PublicIdentity, Error> {
+        let pkey = PKey::public_key_from_pem(pem.as_ref())?;
+
+        Self::from_spki_pkey(pkey)
+    }
+
+    fn from_spki_pkey(pkey: PKey<Public>) -> Result<PublicIdentity, Error> {
+        if pkey.id() != Id::ED25519 {
+            return Err(Error::UnsupportedScheme);
+        }
+
+        let raw = pkey.raw_public_key()?;
+        if raw.len() != ED25519_PUBLIC_KEY_LEN {
+            return Err(Error::InvalidLength);
+        }
+
+        PublicIdentity::new_with_scheme(SignatureScheme::Ed25519, raw)
+    }
 }
 
 impl Hash for PublicIdentity {
@@ -186,7 +381,8 @@ impl Debug for PublicIdentity {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut f = f.debug_struct("PublicIdentity");
         f.field("id", &self.id);
-        match self.pkey.raw_public_key() {
+        f.field("scheme", &self.scheme);
+        match public_key_bytes(&self.pkey, self.scheme) {
             Ok(pk) => f.field("sign", &pk),
             Err(_) => f.field("sign", &"not available"),
         };
@@ -195,11 +391,43 @@ impl Debug for PublicIdentity {
     }
 }
 
-fn compute_address(public_key: &[u8]) -> Result<DeviceID, Error> {
+/// Raw public key bytes for `pkey`, as used for hashing and interop
+///
+/// Ed25519 keys use their raw 32-byte encoding; RSA keys use their
+/// SubjectPublicKeyInfo DER encoding, since RSA keys have no raw form.
+fn public_key_bytes<T: HasPublic>(
+    pkey: &PKeyRef<T>,
+    scheme: SignatureScheme,
+) -> Result<Vec<u8>, Error> {
+    match scheme {
+        SignatureScheme::Ed25519 => Ok(pkey.raw_public_key()?),
+        SignatureScheme::RsaPssSha256 | SignatureScheme::RsaPssSha512 => {
+            Ok(pkey.public_key_to_der()?)
+        }
+    }
+}
+
+fn pkey_from_public_bytes(
+    scheme: SignatureScheme,
+    bytes: &[u8],
+) -> Result<PKey<Public>, Error> {
+    match scheme {
+        SignatureScheme::Ed25519 => Ok(PKey::public_key_from_raw_bytes(bytes, Id::ED25519)?),
+        SignatureScheme::RsaPssSha256 | SignatureScheme::RsaPssSha512 => {
+            Ok(PKey::public_key_from_der(bytes)?)
+        }
+    }
+}
+
+fn compute_address(scheme: SignatureScheme, public_key: &[u8]) -> Result<DeviceID, Error> {
     let nid = Nid::from_raw(NID_BLAKE2B512);
     let md = MessageDigest::from_nid(nid).expect("no message digest");
 
-    let mut first = openssl::hash::hash(md, public_key)?;
+    let mut tagged = Vec::with_capacity(1 + public_key.len());
+    tagged.push(scheme.tag());
+    tagged.extend_from_slice(public_key);
+
+    let mut first = openssl::hash::hash(md, &tagged)?;
     for _ in 0..31 {
         first = openssl::hash::hash(md, &first)?;
     }
@@ -209,13 +437,117 @@ fn compute_address(public_key: &[u8]) -> Result<DeviceID, Error> {
     ))
 }
 
+fn sign<T: openssl::pkey::HasPrivate>(
+    key: &PKeyRef<T>,
+    scheme: SignatureScheme,
+    data: impl AsRef<[u8]>,
+) -> Result<Vec<u8>, Error> {
+    let mut signer = match scheme.digest() {
+        None => Signer::new_without_digest(key)?,
+        Some(md) => {
+            let mut signer = Signer::new(md, key)?;
+            signer.set_rsa_padding(Padding::PKCS1_PSS)?;
+            signer.set_rsa_mgf1_md(md)?;
+            signer.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)?;
+            signer
+        }
+    };
+
+    let signature = signer.sign_oneshot_to_vec(data.as_ref())?;
+
+    Ok(signature)
+}
+
 fn verify<T: HasPublic>(
     key: &PKeyRef<T>,
+    scheme: SignatureScheme,
     data: impl AsRef<[u8]>,
     signature: impl AsRef<[u8]>,
 ) -> Result<bool, Error> {
-    let mut verifier = Verifier::new_without_digest(key)?;
+    let mut verifier = match scheme.digest() {
+        None => Verifier::new_without_digest(key)?,
+        Some(md) => {
+            let mut verifier = Verifier::new(md, key)?;
+            verifier.set_rsa_padding(Padding::PKCS1_PSS)?;
+            verifier.set_rsa_mgf1_md(md)?;
+            verifier.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)?;
+            verifier
+        }
+    };
+
     let ok = verifier.verify_oneshot(signature.as_ref(), data.as_ref())?;
 
     Ok(ok)
 }
+
+#[cfg(test)]
+mod test {
+    use super::{compute_address, Identity, PublicIdentity, SignatureScheme};
+
+    #[test]
+    fn test_rsa_pss_sign_and_verify() {
+        for scheme in [SignatureScheme::RsaPssSha256, SignatureScheme::RsaPssSha512] {
+            let identity = Identity::generate_with(scheme).unwrap();
+
+            let signature = identity.sign(b"hello").unwrap();
+            assert!(identity.verify(b"hello", &signature).unwrap());
+            assert!(!identity.verify(b"goodbye", &signature).unwrap());
+
+            let public = identity.public_identity().unwrap();
+            assert!(public.verify(b"hello", &signature).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_different_schemes_do_not_collide_on_device_id() {
+        // Two distinct keys sharing the hashing pipeline should still get
+        // distinct DeviceIDs across schemes; hashing the very same key bytes
+        // under two schemes makes sure the domain-separation tag is actually
+        // mixed into the hash.
+        let ed25519 = Identity::generate_with(SignatureScheme::Ed25519).unwrap();
+        let rsa = Identity::generate_with(SignatureScheme::RsaPssSha256).unwrap();
+
+        assert_ne!(ed25519.id(), rsa.id());
+
+        let pk = ed25519.public_identity().unwrap().public_key().to_vec();
+        assert_ne!(
+            compute_address(SignatureScheme::Ed25519, &pk).unwrap(),
+            compute_address(SignatureScheme::RsaPssSha256, &pk).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_public_identity_roundtrip_preserves_scheme() {
+        let identity = Identity::generate_with(SignatureScheme::RsaPssSha256).unwrap();
+        let public = identity.public_identity().unwrap();
+
+        let rebuilt =
+            PublicIdentity::new_with_scheme(public.scheme(), public.public_key()).unwrap();
+
+        assert_eq!(public, rebuilt);
+        assert_eq!(public.id(), identity.id());
+    }
+
+    #[test]
+    fn test_spki_der_pem_roundtrip() {
+        let identity = Identity::generate().unwrap();
+        let public = identity.public_identity().unwrap();
+
+        let der = public.to_spki_der().unwrap();
+        let from_der = PublicIdentity::from_spki_der(&der).unwrap();
+        assert_eq!(public, from_der);
+
+        let pem = public.to_spki_pem().unwrap();
+        let from_pem = PublicIdentity::from_spki_pem(&pem).unwrap();
+        assert_eq!(public, from_pem);
+    }
+
+    #[test]
+    fn test_spki_import_rejects_non_ed25519() {
+        let identity = Identity::generate_with(SignatureScheme::RsaPssSha256).unwrap();
+        let public = identity.public_identity().unwrap();
+
+        let der = public.to_spki_der().unwrap();
+        assert!(PublicIdentity::from_spki_der(&der).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{compute_address, DeviceID, Error, Identity, PublicIdentity};
+
+/// Bytes signed by the old key when authorizing a rotation, canonicalized via
+/// [crate::canonical] so the signature is reproducible regardless of platform
+#[derive(Serialize)]
+struct RotationPayload<'a> {
+    old_id: DeviceID,
+    new_id: DeviceID,
+    new_public_key: &'a [u8],
+}
+
+/// A signed link from a retired [Identity] to its successor
+///
+/// Modeled on the `previous_hash` chaining seen in inscription-style
+/// rotation flows: the OLD key signs over `(old_id, new_id, new_public_key)`
+/// so that anyone holding the old [PublicIdentity] can confirm it authorized
+/// the new one.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RotationRecord {
+    pub old_id: DeviceID,
+    pub new_id: DeviceID,
+    pub new_public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl RotationRecord {
+    /// Confirm that `old` authorized this rotation
+    ///
+    /// Returns `false` (rather than erroring) if the record was not signed
+    /// by `old`, or if `new_public_key` does not hash to `new_id` under
+    /// `compute_address` — both are treated as "not authorized" rather than
+    /// fatal errors, mirroring [PublicIdentity::verify].
+    pub fn verify(&self, old: &PublicIdentity) -> Result<bool, Error> {
+        if old.id() != self.old_id {
+            return Ok(false);
+        }
+
+        let expected_new_id = compute_address(old.scheme(), &self.new_public_key)?;
+        if expected_new_id != self.new_id {
+            return Ok(false);
+        }
+
+        let payload = RotationPayload {
+            old_id: self.old_id,
+            new_id: self.new_id,
+            new_public_key: &self.new_public_key,
+        };
+
+        old.verify_canonical(&payload, &self.signature)
+    }
+}
+
+impl Identity {
+    /// Rotate this Identity into a freshly generated successor, keeping the
+    /// same [crate::SignatureScheme]
+    ///
+    /// Returns the new `Identity` together with a [RotationRecord] signed by
+    /// this (old) Identity, proving it authorized the successor.
+    pub fn rotate(&self) -> Result<(Identity, RotationRecord), Error> {
+        let new_identity = Identity::generate_with(self.scheme)?;
+        let new_public = new_identity.public_identity()?;
+
+        let payload = RotationPayload {
+            old_id: self.id,
+            new_id: new_identity.id,
+            new_public_key: new_public.public_key(),
+        };
+        let signature = self.sign_canonical(&payload)?;
+
+        let record = RotationRecord {
+            old_id: self.id,
+            new_id: new_identity.id,
+            new_public_key: new_public.public_key().to_vec(),
+            signature,
+        };
+
+        Ok((new_identity, record))
+    }
+}
+
+/// An ordered chain of [RotationRecord]s linking a root [PublicIdentity] to
+/// its current, authoritative successor
+pub struct RotationChain {
+    records: Vec<RotationRecord>,
+}
+
+impl RotationChain {
+    /// Wrap an ordered sequence of rotation records
+    pub fn new(records: Vec<RotationRecord>) -> RotationChain {
+        RotationChain { records }
+    }
+
+    /// Walk the chain starting at `root`, verifying every link's signature
+    /// and that each record's `old_id` matches the prior link's `new_id`
+    ///
+    /// Rejects cycles (a `new_id` that reappears later in the chain) and
+    /// breaks (an unsigned or mismatched link). Returns the [DeviceID] of
+    /// the current, authoritative identity.
+    pub fn verify(&self, root: &PublicIdentity) -> Result<DeviceID, Error> {
+        let mut current = root.clone();
+
+        let mut seen = HashSet::new();
+        seen.insert(current.id());
+
+        for record in &self.records {
+            if record.old_id != current.id() {
+                return Err(Error::BrokenRotationChain);
+            }
+
+            if !record.verify(&current)? {
+                return Err(Error::BrokenRotationChain);
+            }
+
+            if !seen.insert(record.new_id) {
+                return Err(Error::RotationCycle);
+            }
+
+            current = PublicIdentity::new_with_scheme(current.scheme(), &record.new_public_key)?;
+        }
+
+        Ok(current.id())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Error, Identity};
+
+    use super::{RotationChain, RotationPayload, RotationRecord};
+
+    #[test]
+    fn test_rotation_chain_roundtrip() {
+        let root = Identity::generate().unwrap();
+        let root_public = root.public_identity().unwrap();
+
+        let (gen1, record1) = root.rotate().unwrap();
+        let (gen2, record2) = gen1.rotate().unwrap();
+
+        let chain = RotationChain::new(vec![record1, record2]);
+        let current = chain.verify(&root_public).unwrap();
+
+        assert_eq!(current, gen2.id());
+    }
+
+    #[test]
+    fn test_rejects_public_key_not_hashing_to_new_id() {
+        let root = Identity::generate().unwrap();
+        let root_public = root.public_identity().unwrap();
+
+        let (_gen1, mut record) = root.rotate().unwrap();
+        record.new_public_key[0] ^= 0xff;
+
+        let chain = RotationChain::new(vec![record]);
+        assert!(chain.verify(&root_public).is_err());
+    }
+
+    #[test]
+    fn test_rejects_cycle() {
+        let root = Identity::generate().unwrap();
+        let root_public = root.public_identity().unwrap();
+
+        let (gen1, record1) = root.rotate().unwrap();
+
+        // Hand-craft a second, validly signed record that rotates back to
+        // the root identity, closing a cycle.
+        let back_payload = RotationPayload {
+            old_id: gen1.id(),
+            new_id: root.id(),
+            new_public_key: root_public.public_key(),
+        };
+        let signature = gen1.sign_canonical(&back_payload).unwrap();
+
+        let record2 = RotationRecord {
+            old_id: gen1.id(),
+            new_id: root.id(),
+            new_public_key: root_public.public_key().to_vec(),
+            signature,
+        };
+
+        let chain = RotationChain::new(vec![record1, record2]);
+        assert!(matches!(chain.verify(&root_public), Err(Error::RotationCycle)));
+    }
+}
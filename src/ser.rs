@@ -7,7 +7,11 @@ use serde::de::Unexpected;
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::{DeviceID, Identity, PublicIdentity};
+use crate::{DeviceID, Identity, PublicIdentity, SignatureScheme};
+
+fn default_scheme() -> String {
+    SignatureScheme::Ed25519.to_string()
+}
 
 impl Serialize for Identity {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -18,6 +22,8 @@ impl Serialize for Identity {
         let id = hex::encode(&*self.id);
         s.serialize_field("id", &id)?;
 
+        s.serialize_field("scheme", &self.scheme.to_string())?;
+
         let sign_key = STANDARD.encode(&self.raw_sign);
         s.serialize_field("sign", &sign_key)?;
 
@@ -34,6 +40,8 @@ impl<'de> Deserialize<'de> for Identity {
         #[derive(Deserialize)]
         struct IdentityPlain {
             id: String,
+            #[serde(default = "default_scheme")]
+            scheme: String,
             sign: String,
         }
 
@@ -42,13 +50,28 @@ impl<'de> Deserialize<'de> for Identity {
         let id = DeviceID::from_str(&identity.id)
             .map_err(|_| Error::invalid_value(Unexpected::Str(&identity.id), &"device id"))?;
 
+        let scheme = SignatureScheme::from_str(&identity.scheme)
+            .map_err(|_| Error::invalid_value(Unexpected::Str(&identity.scheme), &"scheme"))?;
+
         let raw_sign = STANDARD.decode(&identity.sign).map_err(|_| {
             Error::invalid_value(Unexpected::Str(&identity.sign), &"base64 encoded")
         })?;
-        let pkey = PKey::private_key_from_raw_bytes(&raw_sign, Id::ED25519)
-            .map_err(|e| Error::custom(format!("{}", e)))?;
-
-        Ok(Identity { id, raw_sign, pkey })
+        let pkey = match scheme {
+            SignatureScheme::Ed25519 => {
+                PKey::private_key_from_raw_bytes(&raw_sign, Id::ED25519)
+                    .map_err(|e| Error::custom(format!("{}", e)))?
+            }
+            SignatureScheme::RsaPssSha256 | SignatureScheme::RsaPssSha512 => {
+                PKey::private_key_from_der(&raw_sign).map_err(|e| Error::custom(format!("{}", e)))?
+            }
+        };
+
+        Ok(Identity {
+            id,
+            scheme,
+            raw_sign,
+            pkey,
+        })
     }
 }
 
@@ -60,6 +83,8 @@ impl Serialize for PublicIdentity {
         let mut s = serializer.serialize_struct("PublicIdentity", 3)?;
         s.serialize_field("id", &self.id)?;
 
+        s.serialize_field("scheme", &self.scheme.to_string())?;
+
         let sign = STANDARD.encode(&self.raw_sign);
         s.serialize_field("sign", &sign)?;
 
@@ -76,19 +101,30 @@ impl<'de> Deserialize<'de> for PublicIdentity {
         #[derive(Deserialize)]
         struct IdentityPlain {
             id: DeviceID,
+            #[serde(default = "default_scheme")]
+            scheme: String,
             sign: String,
         }
 
         let identity: IdentityPlain = IdentityPlain::deserialize(deserializer)?;
 
+        let scheme = SignatureScheme::from_str(&identity.scheme)
+            .map_err(|_| Error::invalid_value(Unexpected::Str(&identity.scheme), &"scheme"))?;
+
         let raw_sign = STANDARD.decode(&identity.sign).map_err(|_| {
             Error::invalid_value(Unexpected::Str(&identity.sign), &"base64 encoded")
         })?;
-        let pkey = PKey::public_key_from_raw_bytes(&raw_sign, Id::ED25519)
-            .map_err(|e| Error::custom(format!("{}", e)))?;
+        let pkey = match scheme {
+            SignatureScheme::Ed25519 => PKey::public_key_from_raw_bytes(&raw_sign, Id::ED25519)
+                .map_err(|e| Error::custom(format!("{}", e)))?,
+            SignatureScheme::RsaPssSha256 | SignatureScheme::RsaPssSha512 => {
+                PKey::public_key_from_der(&raw_sign).map_err(|e| Error::custom(format!("{}", e)))?
+            }
+        };
 
         Ok(PublicIdentity {
             id: identity.id,
+            scheme,
             raw_sign,
             pkey,
         })